@@ -1,20 +1,96 @@
-use super::regex::Regex;
+// parse_float validates its input against the grammar `f64::from_str` accepts, less
+// bare integers and anything containing `:`, then defers to `from_str` itself for the
+// actual conversion. A regex was tried here previously, but `.` in a regex matches any
+// character, `Nan` doesn't cover the canonical `NaN` spelling, and bare integers like
+// `"42"` matched the float pattern too, kept out only because `parse_int` runs first.
+// A small hand-rolled validator avoids all three.
+pub fn parse_float<S: AsRef<str>>(arg: &S) -> Option<f64> {
+    let arg = arg.as_ref();
+    if !is_float_grammar(arg) {
+        return None;
+    }
+    <f64 as ::std::str::FromStr>::from_str(arg).ok()
+}
 
-const FLOAT_STRING: &str = "^([+-]?(inf|Nan|((([0-9]{1,17})|([0-9]{1,17}.[0-9]{1,17})|([0-9]{0,17}.[0-9]{1,17}))[eE]?[+-]?[0-9]{0,17})))$";
+// is_float_grammar walks the string by hand: optional sign, then either one of the
+// special tokens (`inf`, `infinity`, `nan`, case-insensitive) or a mantissa with at
+// least one digit around a single optional `.`, followed by an optional
+// `[eE][+-]?digits` exponent. Socket addresses (which contain `:`) are rejected
+// outright, and a mantissa with neither a `.` nor an exponent (e.g. `"42"`) is
+// rejected so it falls through to `parse_int` instead.
+fn is_float_grammar(arg: &str) -> bool {
+    if arg.is_empty() || arg.contains(':') {
+        return false;
+    }
 
-lazy_static! {
-    static ref FLOAT_NUM_DEC: Regex = Regex::new(FLOAT_STRING).unwrap();
-}
+    let mut chars = arg.chars().peekable();
+    if let Some(&c) = chars.peek() {
+        if c == '+' || c == '-' {
+            chars.next();
+        }
+    }
+    let rest: String = chars.collect();
+    if rest.is_empty() {
+        return false;
+    }
 
-pub fn parse_float<S: AsRef<str>>(arg: &S) -> Option<f64> {
-    FLOAT_NUM_DEC
-        .captures(arg.as_ref())
-        .into_iter()
-        .flat_map(|captures| captures.get(1))
-        .flat_map(|capture_group| {
-            <f64 as ::std::str::FromStr>::from_str(capture_group.as_str()).ok()
-        })
-        .next()
+    let lower = rest.to_lowercase();
+    if lower == "inf" || lower == "infinity" || lower == "nan" {
+        return true;
+    }
+
+    let mut chars = rest.chars().peekable();
+    let mut mantissa_digits = 0usize;
+    let mut seen_dot = false;
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            mantissa_digits += 1;
+            chars.next();
+        } else if c == '.' && !seen_dot {
+            seen_dot = true;
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    if mantissa_digits == 0 {
+        return false;
+    }
+
+    let mut has_exponent = false;
+    let mut exponent_digits = 0usize;
+    if let Some(&c) = chars.peek() {
+        if c == 'e' || c == 'E' {
+            has_exponent = true;
+            chars.next();
+            if let Some(&sign) = chars.peek() {
+                if sign == '+' || sign == '-' {
+                    chars.next();
+                }
+            }
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() {
+                    exponent_digits += 1;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    // anything left over is trailing garbage that doesn't fit the grammar
+    if chars.next().is_some() {
+        return false;
+    }
+    if has_exponent && exponent_digits == 0 {
+        return false;
+    }
+    // an unadorned integer (no `.`, no exponent) belongs to `parse_int`
+    if !seen_dot && !has_exponent {
+        return false;
+    }
+    true
 }
 
 #[test]
@@ -39,3 +115,36 @@ fn test_float_parse() {
     let dut5 = "0.5";
     assert_eq!(parse_float(&dut5), parse_float(&dut4));
 }
+
+#[test]
+fn test_float_parse_special() {
+    let dut0 = "inf";
+    assert_eq!(parse_float(&dut0), Option::Some(::std::f64::INFINITY));
+
+    let dut1 = "-infinity";
+    assert_eq!(parse_float(&dut1), Option::Some(::std::f64::NEG_INFINITY));
+
+    let dut2 = "NaN";
+    assert!(parse_float(&dut2).map(|f| f.is_nan()).unwrap_or(false));
+
+    let dut3 = "nan";
+    assert!(parse_float(&dut3).map(|f| f.is_nan()).unwrap_or(false));
+}
+
+#[test]
+fn test_float_parse_rejects_integers() {
+    let dut0 = "42";
+    assert_eq!(parse_float(&dut0), Option::None);
+
+    let dut1 = "-7";
+    assert_eq!(parse_float(&dut1), Option::None);
+}
+
+#[test]
+fn test_float_parse_rejects_sockets() {
+    let dut0 = "127.0.0.1:8080";
+    assert_eq!(parse_float(&dut0), Option::None);
+
+    let dut1 = "fe80::1:443";
+    assert_eq!(parse_float(&dut1), Option::None);
+}
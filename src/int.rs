@@ -1,13 +1,22 @@
 use super::regex::Regex;
 
 lazy_static! {
-    static ref DEC: Regex = Regex::new(r#"^([-|+]?[0-9]{1,19})$"#).unwrap();
-    static ref HEX: Regex = Regex::new(r#"^0x([A-Fa-f0-9]{1,16})$"#).unwrap();
-    static ref OCTAL: Regex = Regex::new(r#"^0o([0-7]{1,32})$"#).unwrap();
-    static ref BOOL: Regex = Regex::new(r#"^0b([01]{1,64})$"#).unwrap();
+    static ref DEC: Regex = Regex::new(r#"^([-+]?[0-9]+)$"#).unwrap();
+    static ref HEX: Regex = Regex::new(r#"^0x([A-Fa-f0-9]+)$"#).unwrap();
+    static ref OCTAL: Regex = Regex::new(r#"^0o([0-7]+)$"#).unwrap();
+    static ref BOOL: Regex = Regex::new(r#"^0b([01]+)$"#).unwrap();
 }
 
-pub fn parse_int<S: AsRef<str>>(arg: &S) -> Option<i64> {
+/// ParsedInt holds the narrowest integer type a matched literal actually fits in.
+/// `parse_int` always tries `i64` first so the common case is unaffected, widening
+/// to `u64` and then `i128` only when the value overflows the narrower type.
+pub enum ParsedInt {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+}
+
+pub fn parse_int<S: AsRef<str>>(arg: &S) -> Option<ParsedInt> {
     Option::None
         .into_iter()
         .chain(parse_base_10(arg))
@@ -17,42 +26,105 @@ pub fn parse_int<S: AsRef<str>>(arg: &S) -> Option<i64> {
         .next()
 }
 
-fn parse_base_10<S: AsRef<str>>(arg: &S) -> Option<i64> {
+fn parse_base_10<S: AsRef<str>>(arg: &S) -> Option<ParsedInt> {
     boilerplate(&DEC, arg.as_ref(), 10)
 }
 
-fn parse_base_16<S: AsRef<str>>(arg: &S) -> Option<i64> {
+fn parse_base_16<S: AsRef<str>>(arg: &S) -> Option<ParsedInt> {
     boilerplate(&HEX, arg.as_ref(), 16)
 }
 
-fn parse_base_8<S: AsRef<str>>(arg: &S) -> Option<i64> {
+fn parse_base_8<S: AsRef<str>>(arg: &S) -> Option<ParsedInt> {
     boilerplate(&OCTAL, arg.as_ref(), 8)
 }
 
-fn parse_base_2<S: AsRef<str>>(arg: &S) -> Option<i64> {
+fn parse_base_2<S: AsRef<str>>(arg: &S) -> Option<ParsedInt> {
     boilerplate(&BOOL, arg.as_ref(), 2)
 }
-fn boilerplate(regex: &Regex, data: &str, base: u32) -> Option<i64> {
+
+// boilerplate no longer caps digit counts in the regexes themselves; instead it
+// leans on the parse attempts to detect overflow, widening from i64 to u64 to i128
+// until one of them fits.
+fn boilerplate(regex: &Regex, data: &str, base: u32) -> Option<ParsedInt> {
     regex
         .captures(data)
         .into_iter()
         .flat_map(|captures| captures.get(1))
-        .flat_map(|group_one| i64::from_str_radix(group_one.as_str(), base).ok())
+        .flat_map(|group_one| {
+            let digits = group_one.as_str();
+            i64::from_str_radix(digits, base)
+                .map(ParsedInt::I64)
+                .or_else(|_| u64::from_str_radix(digits, base).map(ParsedInt::U64))
+                .or_else(|_| i128::from_str_radix(digits, base).map(ParsedInt::I128))
+                .ok()
+        })
         .next()
 }
 
 #[test]
 fn test_parse_base_10() {
     let dut0 = "10";
-    assert_eq!(parse_base_10(&dut0), Some(10i64));
+    assert_eq!(
+        parse_base_10(&dut0).map(|v| match v {
+            ParsedInt::I64(i) => i,
+            _ => panic!("expected I64"),
+        }),
+        Some(10i64)
+    );
     let dut1 = "+10";
-    assert_eq!(parse_base_10(&dut1), Some(10i64));
+    assert_eq!(
+        parse_base_10(&dut1).map(|v| match v {
+            ParsedInt::I64(i) => i,
+            _ => panic!("expected I64"),
+        }),
+        Some(10i64)
+    );
     let dut2 = "-10";
-    assert_eq!(parse_base_10(&dut2), Some(-10i64));
+    assert_eq!(
+        parse_base_10(&dut2).map(|v| match v {
+            ParsedInt::I64(i) => i,
+            _ => panic!("expected I64"),
+        }),
+        Some(-10i64)
+    );
 }
 
 #[test]
 fn test_parse_base_16() {
     let dut0 = "0xA";
-    assert_eq!(parse_base_16(&dut0), Some(10i64));
+    assert_eq!(
+        parse_base_16(&dut0).map(|v| match v {
+            ParsedInt::I64(i) => i,
+            _ => panic!("expected I64"),
+        }),
+        Some(10i64)
+    );
+}
+
+#[test]
+fn test_parse_base_16_widens_to_uint() {
+    let dut0 = "0xFFFFFFFFFFFFFFFF";
+    match parse_base_16(&dut0) {
+        Some(ParsedInt::U64(u)) => assert_eq!(u, ::std::u64::MAX),
+        other => panic!("expected U64, got {}", matches_to_str(other)),
+    }
+}
+
+#[test]
+fn test_parse_base_16_widens_to_int128() {
+    let dut0 = "0xFFFFFFFFFFFFFFFFF";
+    match parse_base_16(&dut0) {
+        Some(ParsedInt::I128(i)) => assert_eq!(i, 0xFFFFFFFFFFFFFFFFFi128),
+        other => panic!("expected I128, got {}", matches_to_str(other)),
+    }
+}
+
+#[cfg(test)]
+fn matches_to_str(val: Option<ParsedInt>) -> &'static str {
+    match val {
+        Some(ParsedInt::I64(_)) => "I64",
+        Some(ParsedInt::U64(_)) => "U64",
+        Some(ParsedInt::I128(_)) => "I128",
+        None => "None",
+    }
 }
@@ -11,9 +11,9 @@
 //! This crate will preform some basic serialization of Envir
 //! Var key-values in the following manner:
 //!
-//! * `0x[a-fA-F0-9]`: Will be converted to `i64`, and be assumed to be hexidecimal.
-//! * `0o[0-7]+`: Will be converted to `i64`, and be assumed to be octal.
-//! * `[+|-]?[0-9]`: Will be converted to `i64`, and assumed to be decimal.
+//! * `0x[a-fA-F0-9]`: Will be converted to the narrowest of `i64`/`u64`/`i128` that fits, and be assumed to be hexidecimal.
+//! * `0o[0-7]+`: Will be converted to the narrowest of `i64`/`u64`/`i128` that fits, and be assumed to be octal.
+//! * `[+|-]?[0-9]`: Will be converted to the narrowest of `i64`/`u64`/`i128` that fits, and assumed to be decimal.
 //! * Most common patterns of floating point (`0.5`, `.5`, `3.14`, `-3.14`, `2.5E10`, etc.) will be converted to `f64`
 //! * IP addresses will be converted to `IpAddr`.
 //! * SocketAddresses (i.e.: `127.0.0.1:666`) will be converted into `SocketAddr`.
@@ -33,12 +33,13 @@ use self::bool::parse_bool;
 mod floats;
 use self::floats::parse_float;
 mod int;
-use self::int::parse_int;
+use self::int::{parse_int, ParsedInt};
 mod socketaddr;
 use self::socketaddr::{parse_ip, parse_socket};
 
 use std::collections::HashMap;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::ffi::{OsStr, OsString};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::sync::Arc;
 
 /// Value contains the pre & post encoding information about a value.
@@ -50,22 +51,53 @@ pub enum Value {
     String(String),
     Bool(bool, String),
     Int(i64, String),
+    /// Holds integer literals that overflow `i64` but fit in `u64`, e.g. `0xFFFFFFFFFFFFFFFF`.
+    Uint(u64, String),
+    /// Holds integer literals that overflow both `i64` and `u64`.
+    Int128(i128, String),
     Float(f64, String),
     SocketAddr(SocketAddr, String),
     IpAddr(IpAddr, String),
-    Array(Box<[Value]>, String),
+    /// The `char` records which configured delimiter (see `EnvirOptions::delimiters`)
+    /// produced this array, alongside the faithfully preserved original string.
+    Array(Box<[Value]>, char, String),
+    /// Holds a raw `OsString` for variables whose name or value could not be
+    /// losslessly converted to UTF-8. These are otherwise dropped entirely by
+    /// `std::env::vars()`, so this variant exists to keep them retrievable.
+    OsString(OsString),
 }
 impl Value {
-    /// as_str will always succeed as it will always fallback to the `String` stored with each value.
+    /// as_str will always succeed as it will always fallback to the `String` stored with each value,
+    /// with the exception of `Value::OsString` which returns `None` when the underlying `OsString`
+    /// isn't valid UTF-8.
     pub fn as_str<'a>(&'a self) -> Option<&'a str> {
         match self {
             &Value::String(ref s)
             | &Value::Bool(_, ref s)
             | &Value::Int(_, ref s)
+            | &Value::Uint(_, ref s)
+            | &Value::Int128(_, ref s)
             | &Value::Float(_, ref s)
             | &Value::SocketAddr(_, ref s)
             | &Value::IpAddr(_, ref s)
-            | &Value::Array(_, ref s) => Some(s),
+            | &Value::Array(_, _, ref s) => Some(s),
+            &Value::OsString(ref o) => o.to_str(),
+        }
+    }
+    /// as_os_str always succeeds, as every variant can be represented as an `OsStr`,
+    /// be it borrowed from the stored `String` or the raw `OsString` itself.
+    pub fn as_os_str<'a>(&'a self) -> &'a OsStr {
+        match self {
+            &Value::String(ref s)
+            | &Value::Bool(_, ref s)
+            | &Value::Int(_, ref s)
+            | &Value::Uint(_, ref s)
+            | &Value::Int128(_, ref s)
+            | &Value::Float(_, ref s)
+            | &Value::SocketAddr(_, ref s)
+            | &Value::IpAddr(_, ref s)
+            | &Value::Array(_, _, ref s) => OsStr::new(s),
+            &Value::OsString(ref o) => o.as_os_str(),
         }
     }
 
@@ -81,6 +113,18 @@ impl Value {
             _ => None,
         }
     }
+    pub fn as_uint<'a>(&'a self) -> Option<&'a u64> {
+        match self {
+            &Value::Uint(ref u, _) => Some(u),
+            _ => None,
+        }
+    }
+    pub fn as_int128<'a>(&'a self) -> Option<&'a i128> {
+        match self {
+            &Value::Int128(ref i, _) => Some(i),
+            _ => None,
+        }
+    }
     pub fn as_float<'a>(&'a self) -> Option<&'a f64> {
         match self {
             &Value::Float(ref f, _) => Some(f),
@@ -93,6 +137,18 @@ impl Value {
             _ => None,
         }
     }
+    pub fn as_socket_v4<'a>(&'a self) -> Option<&'a SocketAddrV4> {
+        match self {
+            &Value::SocketAddr(SocketAddr::V4(ref s), _) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn as_socket_v6<'a>(&'a self) -> Option<&'a SocketAddrV6> {
+        match self {
+            &Value::SocketAddr(SocketAddr::V6(ref s), _) => Some(s),
+            _ => None,
+        }
+    }
     pub fn as_ipv4<'a>(&'a self) -> Option<&'a Ipv4Addr> {
         match self {
             &Value::IpAddr(IpAddr::V4(ref i), _) => Some(i),
@@ -107,6 +163,28 @@ impl Value {
     }
 }
 
+/// EnvirOptions customizes how `Envir::with_options` infers types out of raw
+/// variable values: which characters split a value into an `Array`, and whether
+/// socket/IP inference should be allowed to claim a value ahead of splitting it.
+#[derive(Clone, Debug)]
+pub struct EnvirOptions {
+    /// Characters that split a value into an `Array`. Checked in order; the first
+    /// one present in a value wins. Defaults to `[':']`.
+    pub delimiters: Vec<char>,
+    /// When `true`, `SocketAddr`/`IpAddr` inference is skipped entirely, so a
+    /// value like `127.0.0.1:8080` is split on `:` like any other array instead
+    /// of being claimed as a `SocketAddr`.
+    pub disable_socket_inference: bool,
+}
+impl Default for EnvirOptions {
+    fn default() -> EnvirOptions {
+        EnvirOptions {
+            delimiters: vec![':'],
+            disable_socket_inference: false,
+        }
+    }
+}
+
 /// Envir is a representation of the environment. But a handful of
 /// conventions and opinions are applied to the data which is found
 /// within the environment.
@@ -120,21 +198,33 @@ impl Value {
 #[derive(Clone)]
 pub struct Envir {
     data: Arc<HashMap<String, Value>>,
+    os_data: Arc<HashMap<OsString, Value>>,
 }
 impl Default for Envir {
     fn default() -> Envir {
-        Envir {
-            data: Arc::new(
-                ::std::env::vars()
-                    .map(|(key, value)| (key, Value::new(value)))
-                    .collect::<HashMap<String, Value, _>>(),
-            ),
-        }
+        Envir::with_options(EnvirOptions::default())
     }
 }
 unsafe impl Send for Envir {}
 unsafe impl Sync for Envir {}
 impl Envir {
+    /// with_options builds an `Envir` the same way `Envir::default` does, but lets
+    /// the caller customize array delimiters and opt out of socket/IP inference.
+    pub fn with_options(options: EnvirOptions) -> Envir {
+        let mut data = HashMap::new();
+        let mut os_data = HashMap::new();
+        for (key, value) in ::std::env::vars_os() {
+            let parsed = Value::new_os_with_options(value, &options);
+            if let Ok(key) = key.clone().into_string() {
+                data.insert(key, parsed.clone());
+            }
+            os_data.insert(key, parsed);
+        }
+        Envir {
+            data: Arc::new(data),
+            os_data: Arc::new(os_data),
+        }
+    }
     /// get will return a pointer to a value if one is found.
     pub fn get<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a Value> {
         self.data.as_ref().get(k.as_ref())
@@ -151,12 +241,36 @@ impl Envir {
             .flat_map(|value| value.as_int())
             .next()
     }
+    pub fn get_uint<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a u64> {
+        self.get(k)
+            .into_iter()
+            .flat_map(|value| value.as_uint())
+            .next()
+    }
+    pub fn get_int128<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a i128> {
+        self.get(k)
+            .into_iter()
+            .flat_map(|value| value.as_int128())
+            .next()
+    }
     pub fn get_float<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a f64> {
         self.get(k)
             .into_iter()
             .flat_map(|value| value.as_float())
             .next()
     }
+    pub fn get_socket_v4<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a SocketAddrV4> {
+        self.get(k)
+            .into_iter()
+            .flat_map(|value| value.as_socket_v4())
+            .next()
+    }
+    pub fn get_socket_v6<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a SocketAddrV6> {
+        self.get(k)
+            .into_iter()
+            .flat_map(|value| value.as_socket_v6())
+            .next()
+    }
     pub fn get_ipv4<'a, S: AsRef<str>>(&'a self, k: &S) -> Option<&'a Ipv4Addr> {
         self.get(k)
             .into_iter()
@@ -175,44 +289,185 @@ impl Envir {
             .flat_map(|value| value.as_str())
             .next()
     }
+    /// get_os looks up a value by a raw `OsStr` key, for variables whose name
+    /// isn't representable in UTF-8 and therefore can't be reached through `get`.
+    pub fn get_os<'a, K: AsRef<OsStr>>(&'a self, k: &K) -> Option<&'a Value> {
+        self.os_data.as_ref().get(k.as_ref())
+    }
 }
 
 impl Value {
     // constructs a new value.
     //
     // iterates over the possible value constructors, and lazily constructs the first it encounters
-    fn new(arg: String) -> Self {
-        Option::None
-            .into_iter()
-            .chain(parse_int(&arg).map(|val| Self::Int(val, arg.clone())))
-            .chain(parse_float(&arg).map(|val| Self::Float(val, arg.clone())))
-            .chain(parse_bool(&arg).map(|val| Self::Bool(val, arg.clone())))
-            .chain(parse_socket(&arg).map(|val| Self::SocketAddr(val, arg.clone())))
-            .chain(parse_ip(&arg).map(|val| Self::IpAddr(val, arg.clone())))
-            .chain(Value::split(&arg))
-            .next()
-            .unwrap_or(Self::String(arg))
+    // new_with_options constructs a value, threading `EnvirOptions` down into
+    // socket/IP inference and `split` so nested array elements stay consistent
+    // with the options the caller configured on `Envir::with_options`.
+    fn new_with_options(arg: String, options: &EnvirOptions) -> Self {
+        if let Some(val) = parse_int(&arg) {
+            return match val {
+                ParsedInt::I64(i) => Self::Int(i, arg),
+                ParsedInt::U64(u) => Self::Uint(u, arg),
+                ParsedInt::I128(i) => Self::Int128(i, arg),
+            };
+        }
+        if let Some(val) = parse_float(&arg) {
+            return Self::Float(val, arg);
+        }
+        if let Some(val) = parse_bool(&arg) {
+            return Self::Bool(val, arg);
+        }
+        if !options.disable_socket_inference {
+            if let Some(val) = parse_socket(&arg) {
+                return Self::SocketAddr(val, arg);
+            }
+            if let Some(val) = parse_ip(&arg) {
+                return Self::IpAddr(val, arg);
+            }
+        }
+        if let Some(val) = Value::split(&arg, options) {
+            return val;
+        }
+        Self::String(arg)
     }
 
-    // split handles the operation of splitting a value by `:` a common convention
-    fn split<S: AsRef<str>>(arg: &S) -> Option<Self> {
-        if !arg.as_ref().contains(':') {
-            return None;
+    // new_os_with_options mirrors `new_with_options`, but is fed straight from
+    // `vars_os`. Well-formed UTF-8 still flows through the normal type inference;
+    // anything else is kept as-is rather than being silently dropped.
+    fn new_os_with_options(arg: OsString, options: &EnvirOptions) -> Self {
+        match arg.into_string() {
+            Ok(s) => Value::new_with_options(s, options),
+            Err(os) => Value::OsString(os),
         }
-        let collection = arg
-            .as_ref()
-            .split(':')
+    }
+
+    // split handles splitting a value by the first of `options.delimiters`, in the
+    // order they are listed, that the value contains (`:` by default, matching the
+    // historical convention), recording the delimiter that won alongside the
+    // faithfully preserved original string.
+    fn split<S: AsRef<str>>(arg: &S, options: &EnvirOptions) -> Option<Self> {
+        let raw = arg.as_ref();
+        let delimiter = options
+            .delimiters
+            .iter()
+            .cloned()
+            .find(|delimiter| raw.contains(*delimiter))?;
+        let collection = raw
+            .split(delimiter)
             .map(|item| item.trim())
             .filter(|item| !item.is_empty())
-            .map(|item| Value::new(item.to_string()))
+            .map(|item| Value::new_with_options(item.to_string(), options))
             .collect::<Vec<Value>>();
         match collection.len() {
             0 => None,
             1 => Some(collection[0].clone()),
             _ => Some(Value::Array(
                 collection.into_boxed_slice(),
-                arg.as_ref().to_string(),
+                delimiter,
+                raw.to_string(),
             )),
         }
     }
 }
+
+#[test]
+fn test_split_delimiter_is_chosen_by_vector_order() {
+    // delimiters are tried in `EnvirOptions::delimiters` order, not by which one
+    // appears earliest in the string -- here `,` is checked first even though `:`
+    // is the character that actually occurs first in "a:b,c:d".
+    let options = EnvirOptions {
+        delimiters: vec![',', ':'],
+        disable_socket_inference: false,
+    };
+    match Value::new_with_options("a:b,c:d".to_string(), &options) {
+        Value::Array(items, delimiter, original) => {
+            assert_eq!(delimiter, ',');
+            assert_eq!(original, "a:b,c:d");
+            assert_eq!(items.len(), 2);
+            match &items[0] {
+                &Value::Array(ref inner, inner_delimiter, ref inner_original) => {
+                    assert_eq!(inner_delimiter, ':');
+                    assert_eq!(inner_original, "a:b");
+                    assert_eq!(inner.len(), 2);
+                    assert_eq!(inner[0].as_str(), Some("a"));
+                    assert_eq!(inner[1].as_str(), Some("b"));
+                }
+                other => panic!("expected nested Array, got {:?}", other),
+            }
+        }
+        other => panic!("expected Array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_split_single_element_unwraps_to_scalar() {
+    let options = EnvirOptions::default();
+    // trailing `:` leaves a single non-empty element after filtering, which
+    // should unwrap to the scalar value rather than a one-element Array.
+    let value = Value::new_with_options("42:".to_string(), &options);
+    assert_eq!(value, Value::Int(42, "42".to_string()));
+}
+
+#[test]
+fn test_disable_socket_inference_forces_split() {
+    let options = EnvirOptions {
+        delimiters: vec![':'],
+        disable_socket_inference: true,
+    };
+    match Value::new_with_options("127.0.0.1:8080".to_string(), &options) {
+        Value::Array(items, delimiter, original) => {
+            assert_eq!(delimiter, ':');
+            assert_eq!(original, "127.0.0.1:8080");
+            assert_eq!(items.len(), 2);
+            // with socket/IP inference disabled, "127.0.0.1" no longer becomes
+            // an IpAddr either -- it stays a plain String element.
+            assert_eq!(items[0].as_str(), Some("127.0.0.1"));
+            assert_eq!(items[1].as_int(), Some(&8080i64));
+        }
+        other => panic!("expected Array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_envir_with_options_custom_delimiter() {
+    let key = "ENVIRONMENT_CRATE_TEST_WITH_OPTIONS_DELIM";
+    ::std::env::set_var(key, "a,b,c");
+    let options = EnvirOptions {
+        delimiters: vec![','],
+        disable_socket_inference: false,
+    };
+    let envir = Envir::with_options(options);
+    match envir.get(&key) {
+        Some(&Value::Array(ref items, delimiter, ref original)) => {
+            assert_eq!(delimiter, ',');
+            assert_eq!(original, "a,b,c");
+            assert_eq!(items.len(), 3);
+        }
+        other => panic!("expected Array, got {:?}", other),
+    }
+    ::std::env::remove_var(key);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_value_os_string_as_str_is_none_for_invalid_utf8() {
+    use std::os::unix::ffi::OsStringExt;
+    let raw = OsString::from_vec(vec![0xff, 0xfe]);
+    let value = Value::OsString(raw.clone());
+    assert_eq!(value.as_str(), None);
+    assert_eq!(value.as_os_str(), raw.as_os_str());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_get_os_round_trips_non_utf8_value() {
+    use std::os::unix::ffi::OsStringExt;
+    let key = OsString::from("ENVIRONMENT_CRATE_TEST_GET_OS");
+    let value = OsString::from_vec(vec![0xff, 0xfe]);
+    ::std::env::set_var(&key, &value);
+    let envir = Envir::default();
+    let got = envir.get_os(&key).expect("value should be present");
+    assert_eq!(got.as_str(), None);
+    assert_eq!(got.as_os_str(), value.as_os_str());
+    ::std::env::remove_var(&key);
+}
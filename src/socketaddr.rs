@@ -1,8 +1,69 @@
-use std::net::{IpAddr, SocketAddr};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
 
 pub fn parse_socket<S: AsRef<str>>(arg: &S) -> Option<SocketAddr> {
-    <SocketAddr as ::std::str::FromStr>::from_str(arg.as_ref()).ok()
+    let arg = arg.as_ref();
+    <SocketAddr as ::std::str::FromStr>::from_str(arg)
+        .ok()
+        .or_else(|| parse_scoped_socket_v6(arg).map(SocketAddr::V6))
 }
 pub fn parse_ip<S: AsRef<str>>(arg: &S) -> Option<IpAddr> {
-    <IpAddr as ::std::str::FromStr>::from_str(arg.as_ref()).ok()
+    let arg = arg.as_ref();
+    <IpAddr as ::std::str::FromStr>::from_str(arg)
+        .ok()
+        .or_else(|| parse_scoped_ipv6(arg).map(IpAddr::V6))
+}
+
+// parse_scoped_ipv6 accepts the non-standard `fe80::1%eth0` zone-identifier syntax
+// that `Ipv6Addr::from_str` rejects outright. The zone is only meaningful, when
+// numeric, for resolving a socket's `scope_id`; `Ipv6Addr` itself has no field for
+// it, so here it is simply stripped before parsing the base address.
+fn parse_scoped_ipv6(arg: &str) -> Option<Ipv6Addr> {
+    let pos = arg.find('%')?;
+    let (base, _zone) = arg.split_at(pos);
+    <Ipv6Addr as ::std::str::FromStr>::from_str(base).ok()
+}
+
+// parse_scoped_socket_v6 handles the bracketed `[fe80::1%eth0]:443` form. A numeric
+// zone (e.g. `%2`) is resolved directly into the `SocketAddrV6` `scope_id`; a named
+// zone (e.g. `%eth0`) can't be resolved to an interface index without a syscall, so
+// it's kept only in the original string and `scope_id` is left at `0`.
+fn parse_scoped_socket_v6(arg: &str) -> Option<SocketAddrV6> {
+    if !arg.starts_with('[') {
+        return None;
+    }
+    let end = arg.find(']')?;
+    let host = &arg[1..end];
+    let rest = &arg[end + 1..];
+    if !rest.starts_with(':') {
+        return None;
+    }
+    let port = rest[1..].parse::<u16>().ok()?;
+
+    let (base, zone) = match host.find('%') {
+        Some(pos) => (&host[..pos], Some(&host[pos + 1..])),
+        None => (host, None),
+    };
+    let addr = <Ipv6Addr as ::std::str::FromStr>::from_str(base).ok()?;
+    let scope_id = zone.and_then(|z| z.parse::<u32>().ok()).unwrap_or(0);
+    Some(SocketAddrV6::new(addr, port, 0, scope_id))
+}
+
+#[test]
+fn test_parse_scoped_ip() {
+    let dut0 = "fe80::1%eth0";
+    assert_eq!(parse_ip(&dut0), Some(IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1))));
+}
+
+#[test]
+fn test_parse_scoped_socket() {
+    let dut0 = "[fe80::1%2]:443";
+    let expect = SocketAddrV6::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 443, 0, 2);
+    assert_eq!(parse_socket(&dut0), Some(SocketAddr::V6(expect)));
+}
+
+#[test]
+fn test_parse_scoped_socket_named_zone() {
+    let dut0 = "[fe80::1%eth0]:443";
+    let expect = SocketAddrV6::new(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1), 443, 0, 0);
+    assert_eq!(parse_socket(&dut0), Some(SocketAddr::V6(expect)));
 }